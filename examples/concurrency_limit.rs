@@ -39,6 +39,12 @@ fn main() {
       WaitAnyResult::Subprocess(id, r) => {
         println!("Process \"sleep {} # {}\" finished: {:?}", id.1, id.0, r);
       }
+      WaitAnyResult::SubprocessOutput(id, r) => {
+        println!("Process \"sleep {} # {}\" finished: {:?}", id.1, id.0, r);
+      }
+      WaitAnyResult::Timeout => {
+        // wait_any never passes a deadline, so this is unreachable.
+      }
     }
   }
 }
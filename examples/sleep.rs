@@ -29,6 +29,12 @@ fn main() {
       WaitAnyResult::Subprocess(id, r) => {
         println!("Process {} finished: {:?}", id, r);
       }
+      WaitAnyResult::SubprocessOutput(id, r) => {
+        println!("Process {} finished: {:?}", id, r);
+      }
+      WaitAnyResult::Timeout => {
+        // wait_any never passes a deadline, so this is unreachable.
+      }
     }
   }
 }
@@ -7,4 +7,8 @@ pub enum Error {
   UnableToSpawnProcess(#[source] io::Error),
   #[error("Wait failed")]
   WaitFailed(#[source] io::Error),
+  #[error("Failed to read subprocess output")]
+  OutputReadFailed(#[source] io::Error),
+  #[error("A subprocess output capture thread panicked")]
+  OutputThreadPanicked,
 }
@@ -6,6 +6,10 @@
 //! * Handles catching ctrl+C
 //! * Allows you to signal all spawned processes to terminate, for example in case
 //!   any one of them fails.
+//!
+//! Unix and Windows are both supported, though some functionality (notably
+//! whole-process-group signalling) differs in what it can do on each platform;
+//! see the relevant methods' docs for details.
 
 mod processset;
 pub use processset::{ProcessSet, WaitAnyResult};
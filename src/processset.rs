@@ -1,31 +1,182 @@
 use std::{
-  collections::HashMap,
+  collections::{HashMap, HashSet},
   fmt::Debug,
   hash::Hash,
-  io,
-  process::{Child, Command, ExitStatus},
+  io::{self, Read},
+  process::{Child, Command, ExitStatus, Output, Stdio},
+  thread::{self, JoinHandle},
+  time::{Duration, Instant},
 };
 
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+
 use crate::{Error, SignalHandler};
 
+/// A running child together with, on Linux, a pidfd that lets [`ProcessSet`]
+/// learn it has exited without scanning every other running child on each
+/// SIGCHLD, and, if spawned via
+/// [`add_command_with_output`](ProcessSet::add_command_with_output), the
+/// threads draining its stdout/stderr pipes.
+#[derive(Debug)]
+struct RunningProcess {
+  child: Child,
+  #[cfg(unix)]
+  pidfd: Option<PidFd>,
+  output: Option<OutputCapture>,
+}
+
+/// Reader threads draining a piped child's stdout/stderr as it runs, so that a
+/// child that fills its pipe buffer can't deadlock the rest of the set while
+/// other children are still being waited on.
+#[derive(Debug)]
+struct OutputCapture {
+  stdout: JoinHandle<io::Result<Vec<u8>>>,
+  stderr: JoinHandle<io::Result<Vec<u8>>>,
+}
+
+impl OutputCapture {
+  fn spawn(child: &mut Child) -> Self {
+    let mut stdout = child.stdout.take().expect("stdout was piped");
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+    OutputCapture {
+      stdout: thread::spawn(move || {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf)?;
+        Ok(buf)
+      }),
+      stderr: thread::spawn(move || {
+        let mut buf = Vec::new();
+        stderr.read_to_end(&mut buf)?;
+        Ok(buf)
+      }),
+    }
+  }
+
+  /// Joins both reader threads and assembles the final `Output`. Should only
+  /// be called after the child has already been waited on, so the threads are
+  /// at (or immediately reach) EOF.
+  fn join(self, status: ExitStatus) -> Result<Output, Error> {
+    let stdout = self
+      .stdout
+      .join()
+      .map_err(|_| Error::OutputThreadPanicked)?
+      .map_err(Error::OutputReadFailed)?;
+    let stderr = self
+      .stderr
+      .join()
+      .map_err(|_| Error::OutputThreadPanicked)?
+      .map_err(Error::OutputReadFailed)?;
+    Ok(Output {
+      status,
+      stdout,
+      stderr,
+    })
+  }
+}
+
+/// An owned pidfd (see pidfd_open(2)), closed on drop.
+#[cfg(unix)]
+#[derive(Debug)]
+struct PidFd(RawFd);
+
+#[cfg(unix)]
+impl PidFd {
+  fn raw(&self) -> RawFd {
+    self.0
+  }
+}
+
+#[cfg(unix)]
+impl Drop for PidFd {
+  fn drop(&mut self) {
+    unsafe {
+      libc::close(self.0);
+    }
+  }
+}
+
+/// Opens a pidfd for `pid` (see pidfd_open(2)), returning `Ok(None)` if the
+/// kernel doesn't support it (pre-5.3) so callers can fall back to scanning on
+/// SIGCHLD instead.
+#[cfg(target_os = "linux")]
+fn pidfd_open(pid: libc::pid_t) -> io::Result<Option<RawFd>> {
+  let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+  if fd >= 0 {
+    Ok(Some(fd as RawFd))
+  } else {
+    let err = io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::ENOSYS) {
+      Ok(None)
+    } else {
+      Err(err)
+    }
+  }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn pidfd_open(_pid: libc::pid_t) -> io::Result<Option<RawFd>> {
+  Ok(None)
+}
+
 #[derive(Debug)]
 pub struct ProcessSet<K> {
   concurrency_limit: Option<usize>,
+  use_process_groups: bool,
+  /// Once a pidfd_open call fails with anything other than success, we stop
+  /// trying and rely solely on the SIGCHLD-scan path for the rest of this set's
+  /// lifetime.
+  #[cfg(unix)]
+  pidfd_unsupported: bool,
+  /// Keys queued via [`add_command_with_output`](Self::add_command_with_output),
+  /// consumed as each one is spawned.
+  capture_output_keys: HashSet<K>,
   queued_keys: HashMap<K, Command>,
-  running_keys: HashMap<K, Child>,
+  running_keys: HashMap<K, RunningProcess>,
   errored_keys: HashMap<K, Error>,
 }
 
 pub enum WaitAnyResult<K> {
   Subprocess(K, Result<(Child, ExitStatus), Error>),
+  /// A process spawned via
+  /// [`add_command_with_output`](ProcessSet::add_command_with_output)
+  /// finished; carries its fully-buffered stdout/stderr alongside its exit
+  /// status, akin to [`Child::wait_with_output`].
+  SubprocessOutput(K, Result<Output, Error>),
   ReceivedTerminationSignal(i32),
   NoProcessesRunning,
+  /// No process finished and no termination signal arrived before the
+  /// deadline passed to [`ProcessSet::wait_any_timeout`] elapsed.
+  Timeout,
+}
+
+/// Outcome of blocking until some running child may have exited, a
+/// termination signal arrives, or a deadline elapses.
+enum BlockOutcome<K> {
+  Activity(ActivityHint<K>),
+  ReceivedTerminationSignal(i32),
+  TimedOut,
+}
+
+/// Which running children, if any, `block_until_activity` learned might have
+/// exited.
+enum ActivityHint<K> {
+  /// The poll told us exactly which child(ren) became ready; only these need
+  /// `try_wait()`.
+  Keys(Vec<K>),
+  /// Something else woke us up (a SIGCHLD with no per-child pidfd info, or an
+  /// unrecognised wakeup); the caller must scan every running child.
+  Unknown,
 }
 
 impl<K> ProcessSet<K> {
   pub fn new() -> Self {
     ProcessSet {
       concurrency_limit: None,
+      use_process_groups: false,
+      #[cfg(unix)]
+      pidfd_unsupported: false,
+      capture_output_keys: HashSet::new(),
       queued_keys: HashMap::new(),
       running_keys: HashMap::new(),
       errored_keys: HashMap::new(),
@@ -37,6 +188,26 @@ impl<K> ProcessSet<K> {
     n.concurrency_limit = Some(limit);
     n
   }
+
+  /// Spawn every child into its own process group, and make
+  /// [`sigint_all`](Self::sigint_all) and [`sigkill_all`](Self::sigkill_all)
+  /// signal the whole group instead of just the direct child.
+  ///
+  /// This matters when a queued `Command` is itself a wrapper (e.g. a shell
+  /// invocation) that spawns further children: without this, those
+  /// grandchildren are never signalled and can outlive the process that was
+  /// supposed to own them.
+  ///
+  /// On Windows, this places each child in a new process group so that
+  /// [`sigint_all`](Self::sigint_all) can target it with
+  /// `GenerateConsoleCtrlEvent`; `sigint_all` returns an error if this wasn't
+  /// enabled. `sigkill_all` on Windows only terminates the direct child either
+  /// way, since forcibly killing a whole group there needs a job object, which
+  /// this crate doesn't set up yet.
+  pub fn with_process_groups(mut self) -> Self {
+    self.use_process_groups = true;
+    self
+  }
 }
 
 fn take_one_from_hashmap<K: Eq + Hash + Clone, V>(hashmap: &mut HashMap<K, V>) -> Option<(K, V)> {
@@ -54,9 +225,73 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
       && self.running_keys.len() < self.concurrency_limit.unwrap_or(usize::max_value())
     {
       let (key, mut command) = take_one_from_hashmap(&mut self.queued_keys).unwrap();
+      #[cfg(unix)]
+      if self.use_process_groups {
+        use std::os::unix::process::CommandExt;
+        // Safety: setpgid(0, 0) only touches the child's own process group and
+        // is async-signal-safe, so it's sound to call between fork and exec.
+        unsafe {
+          command.pre_exec(|| {
+            if libc::setpgid(0, 0) != 0 {
+              return Err(io::Error::last_os_error());
+            }
+            Ok(())
+          });
+        }
+      }
+      #[cfg(windows)]
+      if self.use_process_groups {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+      }
+      let wants_output = self.capture_output_keys.remove(&key);
       let child_res = command.spawn();
-      if let Ok(child) = child_res {
-        self.running_keys.insert(key, child);
+      if let Ok(mut child) = child_res {
+        #[cfg(unix)]
+        if self.use_process_groups {
+          // Mirror pre_exec's setpgid(0, 0) from the parent side too, so that
+          // sigint_all/sigkill_all's kill(-pid, ...) has a process group to
+          // target. std's spawn only returns once the child has exec'd, by
+          // which point pre_exec has already made it a group leader; this
+          // call then fails with EACCES, and its result is ignored.
+          let pid: libc::pid_t = child.id().try_into().unwrap();
+          unsafe {
+            libc::setpgid(pid, pid);
+          }
+        }
+        let output = if wants_output {
+          Some(OutputCapture::spawn(&mut child))
+        } else {
+          None
+        };
+        #[cfg(unix)]
+        let pidfd = if self.pidfd_unsupported {
+          None
+        } else {
+          match pidfd_open(child.id() as libc::pid_t) {
+            Ok(fd) => {
+              if fd.is_none() {
+                self.pidfd_unsupported = true;
+              }
+              fd.map(PidFd)
+            }
+            Err(_) => {
+              // Treat unexpected pidfd_open errors the same as "unsupported"
+              // rather than failing a spawn that otherwise succeeded.
+              self.pidfd_unsupported = true;
+              None
+            }
+          }
+        };
+        self.running_keys.insert(
+          key,
+          RunningProcess {
+            child,
+            #[cfg(unix)]
+            pidfd,
+            output,
+          },
+        );
       } else {
         self
           .errored_keys
@@ -76,6 +311,26 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
     self.spawn_processes();
   }
 
+  /// Like [`add_command`](Self::add_command), but forces the command's
+  /// stdout/stderr to be piped and, once it finishes, buffers them into an
+  /// `Output` returned via `WaitAnyResult::SubprocessOutput` instead of
+  /// `WaitAnyResult::Subprocess`. The pipes are drained by dedicated reader
+  /// threads as the process runs, so a child that fills its pipe buffer can't
+  /// deadlock waiting on the rest of the set.
+  pub fn add_command_with_output(&mut self, key: K, mut command: Command) {
+    if self.queued_keys.contains_key(&key)
+      || self.running_keys.contains_key(&key)
+      || self.errored_keys.contains_key(&key)
+    {
+      panic!("ProcessSet::add_command_with_output: key already exists");
+    }
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    self.capture_output_keys.insert(key.clone());
+    self.queued_keys.insert(key, command);
+    self.spawn_processes();
+  }
+
   /// Wait for any process to finish, and return the corrosponding key and resulting child (or error).
   ///
   /// Takes in a signal handler from outside which can be created with
@@ -96,24 +351,233 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
     if let Some(res) = self.try_wait_any() {
       return res;
     }
+    loop {
+      match self.block_until_activity(signal_handler, None) {
+        BlockOutcome::Activity(hint) => {
+          if let Some(res) = self.try_wait_any_hinted(hint) {
+            return res;
+          }
+        }
+        BlockOutcome::ReceivedTerminationSignal(sig) => {
+          return WaitAnyResult::ReceivedTerminationSignal(sig)
+        }
+        BlockOutcome::TimedOut => unreachable!("wait_any never passes a deadline"),
+      }
+    }
+  }
+
+  /// Like [`wait_any`](Self::wait_any), but gives up and returns
+  /// `WaitAnyResult::Timeout` if no process finishes and no termination signal
+  /// arrives within `timeout`.
+  ///
+  /// The remaining budget is recomputed across spurious wakeups (for example a
+  /// SIGCHLD belonging to a child that turned out to already be reaped), so a
+  /// slow trickle of unrelated signals cannot make this return later than
+  /// `timeout` after the call started.
+  pub fn wait_any_timeout(
+    &mut self,
+    signal_handler: &mut SignalHandler,
+    timeout: Duration,
+  ) -> WaitAnyResult<K> {
+    if let Some(res) = self.try_wait_any() {
+      return res;
+    }
+    let deadline = Instant::now() + timeout;
+    loop {
+      match self.block_until_activity(signal_handler, Some(deadline)) {
+        BlockOutcome::Activity(hint) => {
+          if let Some(res) = self.try_wait_any_hinted(hint) {
+            return res;
+          }
+        }
+        BlockOutcome::ReceivedTerminationSignal(sig) => {
+          return WaitAnyResult::ReceivedTerminationSignal(sig)
+        }
+        BlockOutcome::TimedOut => {
+          // Do one final check in case something finished just as the deadline passed.
+          return self.try_wait_any().unwrap_or(WaitAnyResult::Timeout);
+        }
+      }
+    }
+  }
+
+  // This and the `#[cfg(windows)]` block_until_activity below are kept as two
+  // separate cfg-gated methods rather than behind a shared `PlatformWait`
+  // trait: the unix side waits on raw fds via `libc::poll` and the windows
+  // side waits on handles via `WaitForMultipleObjects`, so a unifying trait
+  // would mostly be a matching pair of one-method impls with no shared logic
+  // to factor out, while adding an extra layer of indirection to step
+  // through. If a third backend (or more shared plumbing between these two)
+  // ever shows up, that's the point to introduce the trait.
+  /// Blocks until either some running child may have exited, a termination
+  /// signal arrives, or (if `deadline` is given) time runs out.
+  ///
+  /// When every running child has a pidfd (Linux >= 5.3), those are polled
+  /// directly alongside the signal fd, so only the child(ren) that actually
+  /// became ready need to be reaped by the caller's subsequent
+  /// [`try_wait_any_hinted`](Self::try_wait_any_hinted) call. Otherwise this falls back to
+  /// waking on every SIGCHLD and letting `try_wait_any` scan.
+  #[cfg(unix)]
+  fn block_until_activity(
+    &mut self,
+    signal_handler: &mut SignalHandler,
+    deadline: Option<Instant>,
+  ) -> BlockOutcome<K> {
     use signal_hook::consts::SIGCHLD;
     loop {
-      let mut has_sigchld = false;
-      let mut has_term = None;
-      for sig in signal_handler.signals.wait() {
-        if sig == SIGCHLD {
-          has_sigchld = true;
-        } else if signal_handler.termination_signals.contains(&sig) {
-          has_term = Some(sig);
+      let timeout_ms = match deadline {
+        Some(deadline) => {
+          let remaining = deadline.saturating_duration_since(Instant::now());
+          if remaining.is_zero() {
+            return BlockOutcome::TimedOut;
+          }
+          remaining.as_millis().min(libc::c_int::MAX as u128) as libc::c_int
         }
+        None => -1,
+      };
+
+      let pidfds = self.running_pidfds();
+      let mut pollfds = Vec::with_capacity(1 + pidfds.as_ref().map_or(0, Vec::len));
+      pollfds.push(libc::pollfd {
+        fd: signal_handler.wake_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+      });
+      if let Some(pidfds) = &pidfds {
+        pollfds.extend(pidfds.iter().map(|&(_, fd)| libc::pollfd {
+          fd,
+          events: libc::POLLIN,
+          revents: 0,
+        }));
       }
-      if let Some(sig) = has_term {
-        return WaitAnyResult::ReceivedTerminationSignal(sig);
+
+      let poll_res =
+        unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout_ms) };
+      if poll_res < 0 {
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::Interrupted {
+          continue;
+        }
+        // Treat an unexpected poll error as a spurious wakeup; the caller's
+        // try_wait_any will pick up anything that actually changed.
+        return BlockOutcome::Activity(ActivityHint::Unknown);
       }
-      if has_sigchld {
-        if let Some(res) = self.try_wait_any() {
-          return res;
+      if poll_res == 0 {
+        return BlockOutcome::TimedOut;
+      }
+
+      // Computed unconditionally: an ordinary child exit makes SIGCHLD *and*
+      // that child's pidfd readable in the same poll() wakeup (the self-pipe
+      // is registered for SIGCHLD regardless of whether every running child
+      // has a pidfd), so pidfd readiness has to be checked before falling
+      // back to the SIGCHLD/Unknown path below, not after.
+      let ready: Vec<K> = match &pidfds {
+        Some(pidfds) => pollfds[1..]
+          .iter()
+          .zip(pidfds.iter())
+          .filter(|(pollfd, _)| pollfd.revents != 0)
+          .map(|(_, (k, _))| k.clone())
+          .collect(),
+        None => Vec::new(),
+      };
+
+      if pollfds[0].revents != 0 {
+        signal_handler.drain_wake_pipe();
+        let mut has_sigchld = false;
+        let mut has_term = None;
+        for sig in signal_handler.signals.pending() {
+          if sig == SIGCHLD {
+            has_sigchld = true;
+          } else if signal_handler.termination_signals.contains(&sig) {
+            has_term = Some(sig);
+          }
+        }
+        if let Some(sig) = has_term {
+          return BlockOutcome::ReceivedTerminationSignal(sig);
+        }
+        if !ready.is_empty() {
+          return BlockOutcome::Activity(ActivityHint::Keys(ready));
+        }
+        if has_sigchld {
+          // SIGCHLD fired but none of our pidfds are ready: either a running
+          // child never got a pidfd (pidfd_open unsupported, or it raced
+          // spawn_processes before pidfd_open ran), or it was already reaped.
+          // Either way we can't narrow it down, so fall back to a full scan.
+          return BlockOutcome::Activity(ActivityHint::Unknown);
+        }
+      } else if !ready.is_empty() {
+        return BlockOutcome::Activity(ActivityHint::Keys(ready));
+      }
+      // Spurious wakeup: the self-pipe was readable but neither SIGCHLD nor a
+      // termination signal was pending (e.g. an earlier `pending()` call such
+      // as termination_pending already consumed it). Keep waiting.
+    }
+  }
+
+  /// Blocks until either some running child's process handle becomes
+  /// signalled, a termination console control event arrives, or (if
+  /// `deadline` is given) time runs out.
+  ///
+  /// This waits on every running child's process handle directly via
+  /// `WaitForMultipleObjects`, which is limited to `MAXIMUM_WAIT_OBJECTS`
+  /// (64) handles, one of which is the termination event; a `ProcessSet` with
+  /// more than 63 children running at once isn't supported on Windows.
+  #[cfg(windows)]
+  fn block_until_activity(
+    &mut self,
+    signal_handler: &mut SignalHandler,
+    deadline: Option<Instant>,
+  ) -> BlockOutcome<K> {
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::{
+      Foundation::{WAIT_FAILED, WAIT_OBJECT_0, WAIT_TIMEOUT},
+      System::Threading::{WaitForMultipleObjects, INFINITE},
+    };
+
+    loop {
+      let timeout_ms = match deadline {
+        Some(deadline) => {
+          let remaining = deadline.saturating_duration_since(Instant::now());
+          if remaining.is_zero() {
+            return BlockOutcome::TimedOut;
+          }
+          remaining.as_millis().min(INFINITE as u128 - 1) as u32
+        }
+        None => INFINITE,
+      };
+
+      let mut handles = Vec::with_capacity(1 + self.running_keys.len());
+      handles.push(signal_handler.termination_event_handle());
+      let mut keys = Vec::with_capacity(self.running_keys.len());
+      for (k, running) in self.running_keys.iter() {
+        handles.push(running.child.as_raw_handle() as isize);
+        keys.push(k.clone());
+      }
+
+      let wait_res =
+        unsafe { WaitForMultipleObjects(handles.len() as u32, handles.as_ptr(), 0, timeout_ms) };
+      if wait_res == WAIT_TIMEOUT {
+        return BlockOutcome::TimedOut;
+      }
+      if wait_res == WAIT_FAILED {
+        // Treat an unexpected wait failure as a spurious wakeup; the caller's
+        // try_wait_any will pick up anything that actually changed.
+        return BlockOutcome::Activity(ActivityHint::Unknown);
+      }
+      if wait_res == WAIT_OBJECT_0 {
+        if let Some(sig) = signal_handler.take_matching_termination_signal() {
+          return BlockOutcome::ReceivedTerminationSignal(sig);
         }
+        // A console event fired but isn't in our termination list. Keep waiting.
+        continue;
+      }
+      let idx = (wait_res - WAIT_OBJECT_0) as usize - 1;
+      match keys.get(idx) {
+        // WaitForMultipleObjects told us exactly which child's handle became
+        // signalled, so there's no need to scan the rest.
+        Some(k) => return BlockOutcome::Activity(ActivityHint::Keys(vec![k.clone()])),
+        None => return BlockOutcome::Activity(ActivityHint::Unknown),
       }
     }
   }
@@ -122,55 +586,137 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
   /// just return None.
   ///
   /// Will never return WaitAnyResult::ReceivedTerminationSignal.
+  ///
+  /// Scans every running child. [`wait_any`](Self::wait_any) and
+  /// [`wait_any_timeout`](Self::wait_any_timeout) avoid this scan when the
+  /// platform backend already knows exactly which child(ren) became ready
+  /// (see [`try_wait_any_hinted`](Self::try_wait_any_hinted)).
   pub fn try_wait_any(&mut self) -> Option<WaitAnyResult<K>> {
+    let keys: Vec<K> = self.running_keys.keys().cloned().collect();
+    self.try_wait_any_among(keys)
+  }
+
+  /// Like [`try_wait_any`](Self::try_wait_any), but only checks the running
+  /// children `block_until_activity` identified as possibly having exited
+  /// (falling back to a full scan if it couldn't narrow it down). This is
+  /// what makes waiting on a large `ProcessSet` scale with the number of
+  /// children that actually exited rather than the number running.
+  fn try_wait_any_hinted(&mut self, hint: ActivityHint<K>) -> Option<WaitAnyResult<K>> {
+    match hint {
+      ActivityHint::Keys(keys) => self.try_wait_any_among(keys),
+      ActivityHint::Unknown => self.try_wait_any(),
+    }
+  }
+
+  /// Checks for any key that failed to spawn, then `try_wait()`s each of
+  /// `keys` in turn, stopping at the first one that has something to report.
+  fn try_wait_any_among(&mut self, keys: impl IntoIterator<Item = K>) -> Option<WaitAnyResult<K>> {
     if let Some((k, e)) = take_one_from_hashmap(&mut self.errored_keys) {
       return Some(WaitAnyResult::Subprocess(k, Err(e)));
     }
     if self.running_keys.is_empty() {
       return Some(WaitAnyResult::NoProcessesRunning);
     }
-    for (k, child) in self.running_keys.iter_mut() {
-      let wait_res = child.try_wait();
-      if let Err(e) = wait_res {
-        let k = k.clone();
-        let taken_k = self.running_keys.remove_entry(&k).unwrap().0;
-        self.spawn_processes();
-        return Some(WaitAnyResult::Subprocess(
-          taken_k,
-          Err(Error::WaitFailed(e)),
-        ));
-      }
-      let wait_res = wait_res.unwrap();
-      if let Some(wait_res) = wait_res {
-        let k = k.clone();
-        let (k, child) = self.running_keys.remove_entry(&k).unwrap();
-        self.spawn_processes();
-        return Some(WaitAnyResult::Subprocess(k, Ok((child, wait_res))));
+    for k in keys {
+      if let Some(res) = self.try_wait_key(k) {
+        return Some(res);
       }
     }
     None
   }
 
-  /// Kills all subprocesses.
+  /// `try_wait()`s a single running child, reaping and removing it from
+  /// `running_keys` if it has exited (or erroring out the same way).
+  fn try_wait_key(&mut self, k: K) -> Option<WaitAnyResult<K>> {
+    let running = self.running_keys.get_mut(&k)?;
+    let wait_res = running.child.try_wait();
+    if let Err(e) = wait_res {
+      let (k, running) = self.running_keys.remove_entry(&k).unwrap();
+      self.spawn_processes();
+      return Some(if running.output.is_some() {
+        WaitAnyResult::SubprocessOutput(k, Err(Error::WaitFailed(e)))
+      } else {
+        WaitAnyResult::Subprocess(k, Err(Error::WaitFailed(e)))
+      });
+    }
+    let wait_res = wait_res.unwrap()?;
+    let (k, running) = self.running_keys.remove_entry(&k).unwrap();
+    self.spawn_processes();
+    if let Some(output) = running.output {
+      Some(WaitAnyResult::SubprocessOutput(k, output.join(wait_res)))
+    } else {
+      Some(WaitAnyResult::Subprocess(k, Ok((running.child, wait_res))))
+    }
+  }
+
+  /// The key and pidfd of all running children, if every one of them has one
+  /// (i.e. pidfd_open succeeded for every child spawned so far, so
+  /// `pidfd_unsupported` is still unset). Used to poll
+  /// directly for, and identify, the exact child(ren) that became ready
+  /// instead of scanning on every SIGCHLD.
+  #[cfg(unix)]
+  fn running_pidfds(&self) -> Option<Vec<(K, RawFd)>> {
+    if self.pidfd_unsupported {
+      return None;
+    }
+    let mut fds = Vec::with_capacity(self.running_keys.len());
+    for (k, running) in self.running_keys.iter() {
+      fds.push((k.clone(), running.pidfd.as_ref()?.raw()));
+    }
+    Some(fds)
+  }
+
+  /// Kills all subprocesses. If [`with_process_groups`](Self::with_process_groups)
+  /// was used, this kills each child's whole process group.
+  ///
+  /// On Windows this only ever terminates the direct child; see
+  /// [`with_process_groups`](Self::with_process_groups).
+  #[cfg(unix)]
   pub fn sigkill_all(&mut self) -> io::Result<()> {
-    for (_, child) in self.running_keys.iter_mut() {
-      child.kill()?;
+    for (_, running) in self.running_keys.iter_mut() {
+      let child = &mut running.child;
+      if self.use_process_groups {
+        let pid: libc::pid_t = child.id().try_into().unwrap();
+        unsafe {
+          if libc::kill(-pid, libc::SIGKILL) != 0 {
+            return Err(io::Error::last_os_error());
+          }
+        }
+      } else {
+        child.kill()?;
+      }
       child.wait()?;
     }
     self.running_keys.clear();
     Ok(())
   }
 
-  /// Send a SIGINT to all subprocesses and return immediately.
+  /// Kills all subprocesses.
+  #[cfg(windows)]
+  pub fn sigkill_all(&mut self) -> io::Result<()> {
+    for (_, running) in self.running_keys.iter_mut() {
+      running.child.kill()?;
+      running.child.wait()?;
+    }
+    self.running_keys.clear();
+    Ok(())
+  }
+
+  /// Send a SIGINT to all subprocesses and return immediately. If
+  /// [`with_process_groups`](Self::with_process_groups) was used, this signals
+  /// each child's whole process group.
+  #[cfg(unix)]
   pub fn sigint_all(&mut self) -> io::Result<()> {
     let mut k_to_remove = Vec::new();
-    for (k, child) in self.running_keys.iter_mut() {
+    for (k, running) in self.running_keys.iter_mut() {
+      let child = &mut running.child;
       if child.try_wait()?.is_none() {
-        let pid = child.id();
+        let pid: libc::pid_t = child.id().try_into().unwrap();
         // Since we have tried to wait the child process and it is still running,
         // the pid we got must be correct.
+        let target_pid = if self.use_process_groups { -pid } else { pid };
         unsafe {
-          if libc::kill(pid.try_into().unwrap(), libc::SIGINT) != 0 {
+          if libc::kill(target_pid, libc::SIGINT) != 0 {
             return Err(io::Error::last_os_error());
           }
         };
@@ -184,6 +730,37 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
     Ok(())
   }
 
+  /// Send a Ctrl+Break event to all subprocesses' process groups and return
+  /// immediately. Requires [`with_process_groups`](Self::with_process_groups),
+  /// since `GenerateConsoleCtrlEvent` can only target a process group, not a
+  /// single process.
+  #[cfg(windows)]
+  pub fn sigint_all(&mut self) -> io::Result<()> {
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+    if !self.use_process_groups {
+      return Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "sigint_all requires with_process_groups() on Windows",
+      ));
+    }
+    let mut k_to_remove = Vec::new();
+    for (k, running) in self.running_keys.iter_mut() {
+      let child = &mut running.child;
+      if child.try_wait()?.is_none() {
+        if unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, child.id()) } == 0 {
+          return Err(io::Error::last_os_error());
+        }
+      } else {
+        k_to_remove.push(k.clone());
+      }
+    }
+    for k in k_to_remove.into_iter() {
+      self.running_keys.remove(&k);
+    }
+    Ok(())
+  }
+
   /// Send a SIGINT to all subprocesses and wait for them to finish.
   pub fn sigint_all_and_wait(&mut self, signal_handler: &mut SignalHandler) -> io::Result<()> {
     self.sigint_all()?;
@@ -195,6 +772,50 @@ impl<K: Hash + Eq + Clone> ProcessSet<K> {
     }
     Ok(())
   }
+
+  /// Send a SIGINT to all subprocesses, give them up to `grace` to exit on
+  /// their own, and forcibly [`sigkill_all`](Self::sigkill_all) whatever is
+  /// still running once `grace` elapses. Unlike
+  /// [`sigint_all_and_wait`](Self::sigint_all_and_wait), this always returns
+  /// within `grace` (plus however long `sigkill_all` itself takes), so a
+  /// child that ignores SIGINT can't hang the caller indefinitely.
+  ///
+  /// Returns the [`Subprocess`](WaitAnyResult::Subprocess)/
+  /// [`SubprocessOutput`](WaitAnyResult::SubprocessOutput) results reaped
+  /// during the grace period, in the order they finished, so a child spawned
+  /// via [`add_command_with_output`](Self::add_command_with_output) that
+  /// exits before `grace` elapses doesn't have its buffered output silently
+  /// thrown away. Children still running when `sigkill_all` fires aren't
+  /// included; their results are lost the same way `sigkill_all` always
+  /// discards them. Nor are children that had already exited, but not yet
+  /// been waited on, when this is called: `sigint_all` drops those without
+  /// reporting them.
+  pub fn terminate_all_with_deadline(
+    &mut self,
+    signal_handler: &mut SignalHandler,
+    grace: Duration,
+  ) -> io::Result<Vec<WaitAnyResult<K>>> {
+    self.sigint_all()?;
+    let deadline = Instant::now() + grace;
+    let mut reaped = Vec::new();
+    while !self.running_keys.is_empty() {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() {
+        break;
+      }
+      let wres = self.wait_any_timeout(signal_handler, remaining);
+      if matches!(
+        wres,
+        WaitAnyResult::Subprocess(..) | WaitAnyResult::SubprocessOutput(..)
+      ) {
+        reaped.push(wres);
+      }
+    }
+    if !self.running_keys.is_empty() {
+      self.sigkill_all()?;
+    }
+    Ok(reaped)
+  }
 }
 
 impl<K> Default for ProcessSet<K> {
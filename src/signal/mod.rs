@@ -0,0 +1,12 @@
+//! Platform-specific delivery of termination signals (SIGINT/SIGTERM on Unix,
+//! console control events on Windows) to [`SignalHandler`].
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+pub use unix::SignalHandler;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use windows::SignalHandler;
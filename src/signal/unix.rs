@@ -0,0 +1,94 @@
+use std::os::unix::{
+  io::{AsRawFd, RawFd},
+  net::UnixStream,
+};
+
+use signal_hook::iterator::{exfiltrator::SignalOnly, SignalsInfo};
+
+#[derive(Debug)]
+pub struct SignalHandler {
+  pub(crate) signals: SignalsInfo<SignalOnly>,
+  /// Read end of a self-pipe that `signal_hook::low_level::pipe::register`
+  /// writes a byte to whenever one of `signals`' signals fires. `SignalsInfo`
+  /// keeps its own internal pipe private, so this is what [`ProcessSet`]
+  /// actually polls on.
+  ///
+  /// [`ProcessSet`]: crate::ProcessSet
+  wake_read: UnixStream,
+  /// Write end, kept around so [`add_termination_signal`](Self::add_termination_signal)
+  /// can register further signals against the same pipe.
+  wake_write: UnixStream,
+  pub(crate) termination_signals: Vec<i32>,
+}
+
+impl Default for SignalHandler {
+  fn default() -> Self {
+    use signal_hook::consts::*;
+    Self::with_termination_signals(&[SIGTERM, SIGINT])
+  }
+}
+
+impl SignalHandler {
+  /// Override the default termination signals list.
+  pub fn with_termination_signals(termination_signals: &[i32]) -> Self {
+    let signals = SignalsInfo::new(termination_signals).unwrap();
+    signals.add_signal(signal_hook::consts::SIGCHLD).unwrap();
+    let (wake_read, wake_write) = UnixStream::pair().unwrap();
+    wake_read.set_nonblocking(true).unwrap();
+    for &signal in termination_signals {
+      signal_hook::low_level::pipe::register(signal, wake_write.try_clone().unwrap()).unwrap();
+    }
+    signal_hook::low_level::pipe::register(
+      signal_hook::consts::SIGCHLD,
+      wake_write.try_clone().unwrap(),
+    )
+    .unwrap();
+    SignalHandler {
+      signals,
+      wake_read,
+      wake_write,
+      termination_signals: termination_signals.to_vec(),
+    }
+  }
+
+  pub fn add_termination_signal(&mut self, signal: i32) {
+    self.termination_signals.push(signal);
+    self.signals.add_signal(signal).unwrap();
+    signal_hook::low_level::pipe::register(signal, self.wake_write.try_clone().unwrap()).unwrap();
+  }
+
+  /// Returns true if there are unprocessed termination signals.
+  ///
+  /// This is useful for checking for termination signals in between different
+  /// stages of processing, so that the application responds fast to signals.
+  pub fn termination_pending(&mut self) -> bool {
+    self
+      .signals
+      .pending()
+      .any(|s| self.termination_signals.contains(&s))
+  }
+
+  /// The fd [`ProcessSet`](crate::ProcessSet) polls alongside running
+  /// children's pidfds to learn that a signal may have arrived, without
+  /// having to busy-poll `signals.pending()`.
+  pub(crate) fn wake_fd(&self) -> RawFd {
+    self.wake_read.as_raw_fd()
+  }
+
+  /// Drains every byte currently buffered in the self-pipe so the next poll
+  /// blocks until a fresh signal arrives.
+  pub(crate) fn drain_wake_pipe(&mut self) {
+    use std::io::Read;
+    let mut buf = [0u8; 64];
+    loop {
+      match self.wake_read.read(&mut buf) {
+        Ok(0) => break,
+        Ok(n) if n < buf.len() => break,
+        Ok(_) => continue,
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+        Err(_) => break,
+      }
+    }
+  }
+}
@@ -0,0 +1,140 @@
+use std::sync::{
+  atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering},
+  OnceLock,
+};
+
+use windows_sys::Win32::System::{
+  Console::{SetConsoleCtrlHandler, CTRL_BREAK_EVENT, CTRL_C_EVENT},
+  Threading::{CreateEventW, ResetEvent, SetEvent},
+};
+
+// The console control handler registered with `SetConsoleCtrlHandler` is a
+// process-wide facility (there is exactly one handler chain, not one per
+// `SignalHandler`), so the event it signals and the last control code it saw
+// have to live in statics, mirroring how `signal_hook` owns the process's
+// signal disposition on Unix.
+static TERMINATION_EVENT: OnceLock<isize> = OnceLock::new();
+static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(-1);
+static HANDLER_INSTALLED: AtomicBool = AtomicBool::new(false);
+/// Bitmask (bit `n` set means control code `n` is one of `termination_signals`)
+/// mirroring the most recently configured `SignalHandler`'s list, so
+/// `ctrl_handler` (which has no access to `self`) can tell whether a given
+/// control code is one we're managing.
+static TERMINATION_SIGNAL_MASK: AtomicU32 = AtomicU32::new(0);
+
+fn signal_bit(signal: i32) -> Option<u32> {
+  u32::try_from(signal).ok().filter(|&s| s < 32).map(|s| 1 << s)
+}
+
+unsafe extern "system" fn ctrl_handler(ctrl_type: u32) -> i32 {
+  PENDING_SIGNAL.store(ctrl_type as i32, Ordering::SeqCst);
+  if let Some(&event) = TERMINATION_EVENT.get() {
+    SetEvent(event);
+  }
+  let mask = TERMINATION_SIGNAL_MASK.load(Ordering::SeqCst);
+  match signal_bit(ctrl_type as i32) {
+    // One of our termination_signals: stop the default action and any
+    // further handlers, same as before.
+    Some(bit) if mask & bit != 0 => 1,
+    // A control code we weren't asked to handle (e.g. CTRL_CLOSE_EVENT when
+    // only CTRL_C_EVENT/CTRL_BREAK_EVENT are configured): let Windows' own
+    // default handling and any other registered handler still run.
+    _ => 0,
+  }
+}
+
+fn termination_event() -> isize {
+  *TERMINATION_EVENT.get_or_init(|| unsafe { CreateEventW(std::ptr::null(), 1, 0, std::ptr::null()) })
+}
+
+fn ensure_handler_installed() {
+  if !HANDLER_INSTALLED.swap(true, Ordering::SeqCst) {
+    termination_event();
+    unsafe {
+      SetConsoleCtrlHandler(Some(ctrl_handler), 1);
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct SignalHandler {
+  pub(crate) termination_signals: Vec<i32>,
+}
+
+impl Default for SignalHandler {
+  fn default() -> Self {
+    Self::with_termination_signals(&[CTRL_C_EVENT as i32, CTRL_BREAK_EVENT as i32])
+  }
+}
+
+impl SignalHandler {
+  /// Override the default termination signals list.
+  ///
+  /// On Windows, signal numbers are the `CTRL_*_EVENT` codes delivered to a
+  /// console control handler (e.g. `CTRL_C_EVENT`, `CTRL_BREAK_EVENT`).
+  pub fn with_termination_signals(termination_signals: &[i32]) -> Self {
+    ensure_handler_installed();
+    let mask = termination_signals
+      .iter()
+      .filter_map(|&s| signal_bit(s))
+      .fold(0, |acc, bit| acc | bit);
+    TERMINATION_SIGNAL_MASK.store(mask, Ordering::SeqCst);
+    SignalHandler {
+      termination_signals: termination_signals.to_vec(),
+    }
+  }
+
+  pub fn add_termination_signal(&mut self, signal: i32) {
+    self.termination_signals.push(signal);
+    if let Some(bit) = signal_bit(signal) {
+      TERMINATION_SIGNAL_MASK.fetch_or(bit, Ordering::SeqCst);
+    }
+  }
+
+  /// Returns true if there are unprocessed termination signals.
+  ///
+  /// This is useful for checking for termination signals in between different
+  /// stages of processing, so that the application responds fast to signals.
+  ///
+  /// Unlike the Unix backend, where `signals.pending()` can report several
+  /// distinct pending signal numbers at once, `PENDING_SIGNAL` is a single
+  /// slot: if two different control codes arrive before either is consumed
+  /// via [`take_matching_termination_signal`](Self::take_matching_termination_signal),
+  /// only the more recent one is retained.
+  pub fn termination_pending(&mut self) -> bool {
+    let pending = PENDING_SIGNAL.load(Ordering::SeqCst);
+    pending >= 0 && self.termination_signals.contains(&pending)
+  }
+
+  /// The manual-reset event that is signalled by the console control handler.
+  /// Used by [`ProcessSet`](crate::ProcessSet) to wait on it alongside running
+  /// children's process handles.
+  pub(crate) fn termination_event_handle(&self) -> isize {
+    termination_event()
+  }
+
+  /// If the pending control code (if any) is one of `termination_signals`,
+  /// consumes and returns it, resetting the event for the next wait.
+  ///
+  /// The event is reset whenever a control code is pending at all, even one
+  /// that isn't in `termination_signals` (e.g. `CTRL_CLOSE_EVENT` when only
+  /// `CTRL_C_EVENT`/`CTRL_BREAK_EVENT` are configured): `TERMINATION_EVENT` is
+  /// manual-reset, so leaving it signalled after an unmatched code would make
+  /// `ProcessSet::block_until_activity`'s `continue` spin forever re-entering
+  /// `WaitForMultipleObjects` against a still-signalled event.
+  pub(crate) fn take_matching_termination_signal(&mut self) -> Option<i32> {
+    let pending = PENDING_SIGNAL.load(Ordering::SeqCst);
+    if pending < 0 {
+      return None;
+    }
+    unsafe {
+      ResetEvent(termination_event());
+    }
+    if self.termination_signals.contains(&pending) {
+      PENDING_SIGNAL.store(-1, Ordering::SeqCst);
+      Some(pending)
+    } else {
+      None
+    }
+  }
+}